@@ -2,26 +2,110 @@ use pyo3::prelude::*;
 use pyo3::types::PyList;
 use pyo3::wrap_pyfunction;
 use once_cell::sync::Lazy;
+use std::path::PathBuf;
 use std::sync::Mutex;
 use tokenizers::Tokenizer;
 use ort::session::Session;
+use ort::execution_providers::{
+    CPUExecutionProvider, CUDAExecutionProvider, CoreMLExecutionProvider,
+    DirectMLExecutionProvider, ExecutionProviderDispatch,
+};
 use ort::value::Value;
 
+mod store;
+
+/// Default sentence-transformer repo used when no model id is given.
+const DEFAULT_MODEL_ID: &str = "sentence-transformers/all-MiniLM-L6-v2";
+
+/// Pooling strategy applied to a model's per-token hidden states.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum PoolingMode {
+    /// Attention-masked mean over the sequence (default).
+    Mean,
+    /// Hidden state at position 0 (the `[CLS]` token).
+    Cls,
+    /// Elementwise max over the sequence.
+    Max,
+}
+
+impl PoolingMode {
+    /// Parse a pooling mode from its lowercase string name.
+    fn from_name(name: &str) -> Result<Self, EmbedError> {
+        match name.to_lowercase().as_str() {
+            "mean" => Ok(PoolingMode::Mean),
+            "cls" => Ok(PoolingMode::Cls),
+            "max" => Ok(PoolingMode::Max),
+            other => Err(EmbedError::Inference(format!("Unknown pooling mode: {}", other))),
+        }
+    }
+}
+
 /// Global model state
 static MODEL_STATE: Lazy<Mutex<ModelState>> = Lazy::new(|| {
     Mutex::new(ModelState {
         session: None,
         tokenizer: None,
+        name: DEFAULT_MODEL_ID.to_string(),
         dimensions: 384,
         max_length: 256,
+        normalize: true,
+        device: "cpu".to_string(),
+        pooling: PoolingMode::Mean,
     })
 });
 
 struct ModelState {
     session: Option<Session>,
     tokenizer: Option<Tokenizer>,
+    name: String,
     dimensions: usize,
     max_length: usize,
+    normalize: bool,
+    /// The execution provider the caller requested, not necessarily the one the
+    /// session bound — ORT may fall back to CPU without reporting it.
+    device: String,
+    pooling: PoolingMode,
+}
+
+/// Options controlling which model is loaded and how it is configured.
+///
+/// Construct in Python and pass to [`init_model`], e.g.
+/// `EmbedderOptions(model_id="BAAI/bge-base-en-v1.5", normalize=True)`.
+#[pyclass]
+#[derive(Clone)]
+struct EmbedderOptions {
+    #[pyo3(get, set)]
+    model_id: String,
+    #[pyo3(get, set)]
+    revision: Option<String>,
+    #[pyo3(get, set)]
+    normalize: bool,
+    #[pyo3(get, set)]
+    max_length: usize,
+    /// Pooling strategy: `"mean"` (default), `"cls"`, or `"max"`.
+    #[pyo3(get, set)]
+    pooling: String,
+}
+
+#[pymethods]
+impl EmbedderOptions {
+    #[new]
+    #[pyo3(signature = (model_id=DEFAULT_MODEL_ID.to_string(), revision=None, normalize=true, max_length=256, pooling="mean".to_string()))]
+    fn new(model_id: String, revision: Option<String>, normalize: bool, max_length: usize, pooling: String) -> Self {
+        Self { model_id, revision, normalize, max_length, pooling }
+    }
+}
+
+impl Default for EmbedderOptions {
+    fn default() -> Self {
+        Self {
+            model_id: DEFAULT_MODEL_ID.to_string(),
+            revision: None,
+            normalize: true,
+            max_length: 256,
+            pooling: "mean".to_string(),
+        }
+    }
 }
 
 /// Custom error type for embedding operations
@@ -45,15 +129,44 @@ impl From<EmbedError> for PyErr {
     }
 }
 
-/// Initialize the Rust embedding engine with ONNX model.
+/// Initialize the Rust embedding engine with an ONNX model.
+///
+/// Either pass an explicit `model_path` to a local `.onnx` file, or an
+/// [`EmbedderOptions`] selecting a sentence-transformer by Hugging Face repo
+/// id. When neither is given the default [`DEFAULT_MODEL_ID`] is loaded from
+/// (or downloaded into) `~/.memento/models`.
 #[pyfunction]
-fn init_model(py: Python, model_path: Option<String>) -> PyResult<PyObject> {
-    let model_path = model_path.unwrap_or_else(|| {
-        let home = std::env::var("HOME").unwrap_or_else(|_| ".".to_string());
-        format!("{}/.memento/models/all-MiniLM-L6-v2.onnx", home)
-    });
-
-    let model_path = std::path::PathBuf::from(model_path);
+#[pyo3(signature = (model_path=None, options=None, providers=None, intra_op_threads=None, inter_op_threads=None))]
+fn init_model(
+    py: Python,
+    model_path: Option<String>,
+    options: Option<EmbedderOptions>,
+    providers: Option<Vec<String>>,
+    intra_op_threads: Option<usize>,
+    inter_op_threads: Option<usize>,
+) -> PyResult<PyObject> {
+    let options = options.unwrap_or_default();
+    let pooling = PoolingMode::from_name(&options.pooling)?;
+    // Default to CPU-only when no preference is given.
+    let providers = providers.unwrap_or_else(|| vec!["cpu".to_string()]);
+
+    // Resolve the ONNX/tokenizer locations: an explicit path wins, otherwise
+    // fetch-on-demand from the Hub by repo id + revision.
+    let (model_path, name) = match model_path {
+        Some(path) => {
+            let path = PathBuf::from(path);
+            let name = path
+                .file_stem()
+                .and_then(|n| n.to_str())
+                .unwrap_or(&options.model_id)
+                .to_string();
+            (path, name)
+        }
+        None => {
+            let (onnx_path, _) = ensure_model_files(&options.model_id, options.revision.as_deref())?;
+            (onnx_path, options.model_id.clone())
+        }
+    };
 
     // Load tokenizer
     let tokenizer_path = model_path
@@ -72,9 +185,9 @@ fn init_model(py: Python, model_path: Option<String>) -> PyResult<PyObject> {
     } else {
         tokenizer.with_padding(Some(tokenizers::PaddingParams::default()));
     }
-    
+
     if let Some(params) = tokenizer.get_truncation_mut() {
-        params.max_length = 256;
+        params.max_length = options.max_length;
         params.strategy = tokenizers::TruncationStrategy::LongestFirst;
     } else {
         let _ = tokenizer.with_truncation(Some(tokenizers::TruncationParams::default()));
@@ -90,50 +203,201 @@ fn init_model(py: Python, model_path: Option<String>) -> PyResult<PyObject> {
         .ok_or_else(|| EmbedError::Io(std::io::Error::new(
             std::io::ErrorKind::InvalidInput, "Invalid model filename"
         )))?;
-    
-    let session = load_model_with_working_dir(model_dir, model_filename)?;
 
-    let dimensions = 384;
-    
-    // Update global state
-    let mut state = MODEL_STATE.lock().map_err(|e| {
+    let (eps, device) = build_execution_providers(&providers);
+    let session = load_model_with_working_dir(
+        model_dir,
+        model_filename,
+        eps,
+        intra_op_threads,
+        inter_op_threads,
+    )?;
+
+    // Update global state. `dimensions` is a placeholder until the warmup
+    // inference below reads the model's real hidden size.
+    {
+        let mut state = MODEL_STATE.lock().map_err(|e| {
+            EmbedError::Inference(format!("Failed to lock model state: {}", e))
+        })?;
+
+        state.session = Some(session);
+        state.tokenizer = Some(tokenizer);
+        state.name = name.clone();
+        state.max_length = options.max_length;
+        state.normalize = options.normalize;
+        state.device = device;
+        state.pooling = pooling;
+    }
+
+    // Warmup inference: populates `ModelState::dimensions` from the real
+    // `[batch, seq_len, hidden]` output shape so any model reports correctly.
+    let _ = embed_batch_internal(&["warmup".to_string()])?;
+
+    let state = MODEL_STATE.lock().map_err(|e| {
         EmbedError::Inference(format!("Failed to lock model state: {}", e))
     })?;
-    
-    state.session = Some(session);
-    state.tokenizer = Some(tokenizer);
-    state.dimensions = dimensions;
 
     // Build result dict
     let info = pyo3::types::PyDict::new_bound(py);
-    info.set_item("name", "all-MiniLM-L6-v2")?;
-    info.set_item("dimensions", dimensions)?;
+    info.set_item("name", &name)?;
+    info.set_item("dimensions", state.dimensions)?;
     info.set_item("backend", "onnx")?;
+    info.set_item("requested_device", &state.device)?;
     info.set_item("version", env!("CARGO_PKG_VERSION"))?;
     info.set_item("status", "loaded")?;
     info.set_item("model_path", model_path.to_str().unwrap_or(""))?;
-    
+
     Ok(info.into())
 }
 
+/// Ensure the ONNX model and tokenizer for `model_id` exist under
+/// `~/.memento/models`, downloading them from the Hugging Face Hub if absent.
+///
+/// Returns the local `(model.onnx, tokenizer.json)` paths.
+fn ensure_model_files(model_id: &str, revision: Option<&str>) -> Result<(PathBuf, PathBuf), EmbedError> {
+    use hf_hub::{api::sync::ApiBuilder, Repo, RepoType};
+
+    let home = std::env::var("HOME").unwrap_or_else(|_| ".".to_string());
+    let cache_dir = PathBuf::from(format!("{}/.memento/models", home));
+    let local_dir = cache_dir.join(model_id.replace('/', "_"));
+    let onnx_path = local_dir.join("model.onnx");
+    let tokenizer_path = local_dir.join("tokenizer.json");
+
+    if onnx_path.exists() && tokenizer_path.exists() {
+        return Ok((onnx_path, tokenizer_path));
+    }
+
+    std::fs::create_dir_all(&local_dir)?;
+
+    let api = ApiBuilder::new()
+        .with_cache_dir(cache_dir)
+        .build()
+        .map_err(|e| EmbedError::Io(std::io::Error::new(
+            std::io::ErrorKind::Other,
+            format!("Failed to build HF Hub client: {}", e),
+        )))?;
+
+    let repo = match revision {
+        Some(rev) => api.repo(Repo::with_revision(model_id.to_string(), RepoType::Model, rev.to_string())),
+        None => api.model(model_id.to_string()),
+    };
+
+    // ONNX exports live either at the repo root or under an `onnx/` subdir.
+    let (fetched_onnx, remote_dir) = match repo.get("onnx/model.onnx") {
+        Ok(path) => (path, "onnx/"),
+        Err(_) => {
+            let path = repo.get("model.onnx").map_err(|e| EmbedError::Io(std::io::Error::new(
+                std::io::ErrorKind::NotFound,
+                format!("Failed to download ONNX model for {}: {}", model_id, e),
+            )))?;
+            (path, "")
+        }
+    };
+    let fetched_tokenizer = repo
+        .get("tokenizer.json")
+        .map_err(|e| EmbedError::Io(std::io::Error::new(
+            std::io::ErrorKind::NotFound,
+            format!("Failed to download tokenizer for {}: {}", model_id, e),
+        )))?;
+
+    std::fs::copy(&fetched_onnx, &onnx_path)?;
+    std::fs::copy(&fetched_tokenizer, &tokenizer_path)?;
+
+    // Larger exports (≥2GB, e.g. some `bge` models) store their weights in a
+    // sibling `model.onnx_data` file that `load_model_with_working_dir`
+    // resolves relative to the model. Fetch it alongside when the repo has one;
+    // single-file exports simply won't have it, so a miss is not an error.
+    if let Ok(fetched_data) = repo.get(&format!("{}model.onnx_data", remote_dir)) {
+        std::fs::copy(&fetched_data, local_dir.join("model.onnx_data"))?;
+    }
+
+    Ok((onnx_path, tokenizer_path))
+}
+
+/// Resolve a list of requested execution provider names (e.g. `["cuda", "cpu"]`)
+/// into `ort` dispatches registered in fallback order, plus the name of the
+/// first recognized *requested* provider.
+///
+/// The returned name reflects the caller's preferred provider, not what the
+/// committed session ultimately bound: ORT may silently fall back (e.g. CUDA to
+/// CPU) at `commit_from_memory`, and that decision isn't exposed here. Callers
+/// should treat it as the requested provider, not a guarantee of acceleration.
+///
+/// Unknown names are ignored. When the list does not already name `"cpu"`, CPU
+/// is appended as a final fallback so a session can bind even when no
+/// accelerator is present.
+fn build_execution_providers(providers: &[String]) -> (Vec<ExecutionProviderDispatch>, String) {
+    let mut dispatches = Vec::new();
+    let mut device: Option<String> = None;
+    let mut saw_cpu = false;
+
+    for name in providers {
+        let canonical = name.to_lowercase();
+        let dispatch = match canonical.as_str() {
+            "cuda" => CUDAExecutionProvider::default().build(),
+            "coreml" => CoreMLExecutionProvider::default().build(),
+            "directml" => DirectMLExecutionProvider::default().build(),
+            "cpu" => {
+                saw_cpu = true;
+                CPUExecutionProvider::default().build()
+            }
+            _ => continue,
+        };
+
+        if device.is_none() {
+            device = Some(canonical.clone());
+        }
+        dispatches.push(dispatch);
+    }
+
+    // Always keep CPU as the ultimate fallback.
+    if !saw_cpu {
+        dispatches.push(CPUExecutionProvider::default().build());
+    }
+
+    (dispatches, device.unwrap_or_else(|| "cpu".to_string()))
+}
+
 /// Load ONNX model by temporarily changing working directory to handle external data files
-fn load_model_with_working_dir(model_dir: &std::path::Path, model_filename: &str) -> Result<Session, EmbedError> {
+fn load_model_with_working_dir(
+    model_dir: &std::path::Path,
+    model_filename: &str,
+    execution_providers: Vec<ExecutionProviderDispatch>,
+    intra_op_threads: Option<usize>,
+    inter_op_threads: Option<usize>,
+) -> Result<Session, EmbedError> {
     let original_dir = std::env::current_dir()
         .map_err(|e| EmbedError::Io(e))?;
-    
+
     // Change to model directory so external data file can be found
     std::env::set_current_dir(model_dir)
         .map_err(|e| EmbedError::Io(e))?;
-    
-    // Load model
-    let result = Session::builder()
-        .map_err(|e| EmbedError::Onnx(format!("Failed to create session builder: {}", e)))?
-        .commit_from_memory(&std::fs::read(model_filename)?)
-        .map_err(|e| EmbedError::Onnx(format!("Failed to load model: {}", e)));
-    
+
+    let result = (|| {
+        let mut builder = Session::builder()
+            .map_err(|e| EmbedError::Onnx(format!("Failed to create session builder: {}", e)))?
+            .with_execution_providers(execution_providers)
+            .map_err(|e| EmbedError::Onnx(format!("Failed to register execution providers: {}", e)))?;
+
+        if let Some(n) = intra_op_threads {
+            builder = builder
+                .with_intra_threads(n)
+                .map_err(|e| EmbedError::Onnx(format!("Failed to set intra-op threads: {}", e)))?;
+        }
+        if let Some(n) = inter_op_threads {
+            builder = builder
+                .with_inter_threads(n)
+                .map_err(|e| EmbedError::Onnx(format!("Failed to set inter-op threads: {}", e)))?;
+        }
+
+        builder
+            .commit_from_memory(&std::fs::read(model_filename)?)
+            .map_err(|e| EmbedError::Onnx(format!("Failed to load model: {}", e)))
+    })();
+
     // Restore original directory
     let _ = std::env::set_current_dir(original_dir);
-    
+
     result
 }
 
@@ -151,15 +415,18 @@ fn get_model_info(py: Python) -> PyResult<PyObject> {
     let info = pyo3::types::PyDict::new_bound(py);
     
     if let Ok(state) = MODEL_STATE.lock() {
-        info.set_item("name", "all-MiniLM-L6-v2")?;
+        info.set_item("name", &state.name)?;
         info.set_item("dimensions", state.dimensions)?;
         info.set_item("max_sequence_length", state.max_length)?;
         info.set_item("backend", "onnx")?;
+        // The provider the caller asked for; ORT may have fallen back to CPU at
+        // commit without surfacing it, so this is a request, not a guarantee.
+        info.set_item("requested_device", &state.device)?;
         info.set_item("version", env!("CARGO_PKG_VERSION"))?;
         info.set_item("ready", state.session.is_some())?;
         info.set_item("status", if state.session.is_some() { "loaded" } else { "not_loaded" })?;
     } else {
-        info.set_item("name", "all-MiniLM-L6-v2")?;
+        info.set_item("name", DEFAULT_MODEL_ID)?;
         info.set_item("dimensions", 384)?;
         info.set_item("max_sequence_length", 256)?;
         info.set_item("backend", "onnx")?;
@@ -179,55 +446,102 @@ fn embed_text(py: Python, text: String) -> PyResult<PyObject> {
     Ok(py_list.into())
 }
 
+/// Soft cap on how many texts are fed through a single `session.run` call.
+///
+/// The ONNX graph supports an arbitrary batch dimension, but padding every
+/// encoding to the batch-longest sequence means one oversized batch can
+/// allocate a very large `[batch, seq_len, hidden]` output. Splitting into
+/// sub-batches of this size keeps peak memory bounded while still using the
+/// real batched path for the common case.
+const MAX_BATCH_SIZE: usize = 64;
+
 /// Embed multiple texts in a batch.
-/// 
-/// Note: Currently processes texts individually due to model batch size constraints.
-/// This ensures correct results for any batch size.
+///
+/// Runs a single ONNX inference over each sub-batch of [`MAX_BATCH_SIZE`]
+/// texts rather than one call per text, which is the dominant cost when
+/// embedding thousands of chunks.
 #[pyfunction]
 fn embed_batch(py: Python, texts: Vec<String>) -> PyResult<PyObject> {
     if texts.is_empty() {
         let empty_list = PyList::empty_bound(py);
         return Ok(empty_list.into());
     }
-    
-    // Process each text individually to avoid batch size constraints
+
     let outer_list = PyList::empty_bound(py);
-    for text in texts {
-        let embedding = embed_text_internal(&text)?;
-        let inner_list: Bound<'_ , PyList> = PyList::new_bound(py, embedding);
-        outer_list.append(inner_list)?;
+    for chunk in texts.chunks(MAX_BATCH_SIZE) {
+        let embeddings = embed_batch_internal(chunk)?;
+        for embedding in embeddings {
+            let inner_list: Bound<'_ , PyList> = PyList::new_bound(py, embedding);
+            outer_list.append(inner_list)?;
+        }
     }
-    
+
     Ok(outer_list.into())
 }
 
 /// Internal function to embed a single text
 fn embed_text_internal(text: &str) -> Result<Vec<f32>, EmbedError> {
     let embeddings = embed_batch_internal(&[text.to_string()])?;
-    Ok(embeddings.into_iter().next().unwrap_or_else(|| vec![0.0; 384]))
+    Ok(embeddings.into_iter().next().unwrap_or_else(|| {
+        let dims = MODEL_STATE.lock().map(|s| s.dimensions).unwrap_or(384);
+        vec![0.0; dims]
+    }))
 }
 
-/// Internal function to embed a batch of texts
-fn embed_batch_internal(texts: &[String]) -> Result<Vec<Vec<f32>>, EmbedError> {
+/// Embed a single text and return its full per-token `[seq_len, hidden]`
+/// matrix instead of a pooled vector, for callers doing their own pooling or
+/// late-interaction scoring downstream.
+#[pyfunction]
+fn embed_text_tokens(py: Python, text: String) -> PyResult<PyObject> {
+    let mut tokens = embed_tokens_internal(&[text])?;
+    let matrix = tokens.pop().unwrap_or_default();
+    let outer = PyList::empty_bound(py);
+    for token in matrix {
+        outer.append(PyList::new_bound(py, token))?;
+    }
+    Ok(outer.into())
+}
+
+/// Embed multiple texts and return, for each, its per-token `[seq_len, hidden]`
+/// matrix (see [`embed_text_tokens`]).
+#[pyfunction]
+fn embed_batch_tokens(py: Python, texts: Vec<String>) -> PyResult<PyObject> {
+    let outer = PyList::empty_bound(py);
+    if texts.is_empty() {
+        return Ok(outer.into());
+    }
+    for chunk in texts.chunks(MAX_BATCH_SIZE) {
+        for matrix in embed_tokens_internal(chunk)? {
+            let text_tokens = PyList::empty_bound(py);
+            for token in matrix {
+                text_tokens.append(PyList::new_bound(py, token))?;
+            }
+            outer.append(text_tokens)?;
+        }
+    }
+    Ok(outer.into())
+}
+
+/// Run inference and return, for each text, its per-token hidden states
+/// (`[real_tokens, hidden]`) with padding positions already stripped using the
+/// attention mask. This is the shared core behind both pooled embeddings and
+/// raw-token output.
+fn embed_tokens_internal(texts: &[String]) -> Result<Vec<Vec<Vec<f32>>>, EmbedError> {
     // Get model state
     let mut state = MODEL_STATE.lock().map_err(|e| {
         EmbedError::Inference(format!("Failed to lock model state: {}", e))
     })?;
 
-    // Auto-initialize if not loaded
+    // Auto-initialize with the default model if not loaded
     if state.session.is_none() || state.tokenizer.is_none() {
         drop(state);
-        
-        let home = std::env::var("HOME").unwrap_or_else(|_| ".".to_string());
-        let model_path = std::path::PathBuf::from(format!(
-            "{}/.memento/models/all-MiniLM-L6-v2.onnx", home
-        ));
-        
+
+        let (model_path, _) = ensure_model_files(DEFAULT_MODEL_ID, None)?;
         let model_dir = model_path.parent()
             .ok_or_else(|| EmbedError::Io(std::io::Error::new(
                 std::io::ErrorKind::InvalidInput, "Invalid model path"
             )))?;
-        
+
         // Load tokenizer
         let tokenizer_path = model_dir.join("tokenizer.json");
 
@@ -239,12 +553,16 @@ fn embed_batch_internal(texts: &[String]) -> Result<Vec<Vec<f32>>, EmbedError> {
         let _ = tokenizer.with_truncation(Some(tokenizers::TruncationParams::default()));
 
         // Load ONNX model
-        let session = load_model_with_working_dir(model_dir, "all-MiniLM-L6-v2.onnx")?;
+        let model_filename = model_path.file_name()
+            .and_then(|n| n.to_str())
+            .unwrap_or("model.onnx");
+        let (eps, _) = build_execution_providers(&["cpu".to_string()]);
+        let session = load_model_with_working_dir(model_dir, model_filename, eps, None, None)?;
 
         state = MODEL_STATE.lock().map_err(|e| {
             EmbedError::Inference(format!("Failed to lock model state: {}", e))
         })?;
-        
+
         state.session = Some(session);
         state.tokenizer = Some(tokenizer);
     }
@@ -324,60 +642,166 @@ fn embed_batch_internal(texts: &[String]) -> Result<Vec<Vec<f32>>, EmbedError> {
     let (shape, hidden_data) = hidden_state_output.try_extract_tensor::<f32>()
         .map_err(|e| EmbedError::Inference(format!("Failed to extract tensor: {}", e)))?;
     
+    // The raw-token path needs a 3-D `[batch, seq_len, hidden]` output. Some
+    // exports emit only a 2-D pooled `sentence_embedding`; reject those with a
+    // clear error rather than indexing past the end of the shape.
+    if shape.len() < 3 {
+        return Err(EmbedError::Inference(format!(
+            "Model output '{}' has rank {}, expected a 3-D [batch, seq_len, hidden] tensor; \
+             pooled-only exports are not supported",
+            output_key,
+            shape.len(),
+        )));
+    }
+
     let seq_len = shape[1] as usize;
     let hidden_size = shape[2] as usize;
 
-    // Perform mean pooling with attention mask
-    let mut embeddings = Vec::with_capacity(batch_size);
-    
+    // Slice the flat `[batch, seq_len, hidden]` buffer into per-text token
+    // matrices, keeping only positions the attention mask marks as real.
+    let mut token_embeddings = Vec::with_capacity(batch_size);
+
     for b in 0..batch_size {
-        let mask_f32: Vec<f32> = encodings[b].get_attention_mask()
-            .iter()
-            .map(|&m| m as f32)
-            .collect();
-        
-        let mut sum_embedding = vec![0.0f32; hidden_size];
-        let mut mask_sum = 0.0f32;
-        
+        let mask = encodings[b].get_attention_mask();
+
+        let mut tokens = Vec::new();
         for s in 0..seq_len {
-            let mask_val = mask_f32.get(s).copied().unwrap_or(0.0);
-            mask_sum += mask_val;
-            
-            for h in 0..hidden_size {
-                let idx = b * seq_len * hidden_size + s * hidden_size + h;
-                let val = hidden_data[idx];
-                sum_embedding[h] += val * mask_val;
+            if mask.get(s).copied().unwrap_or(0) == 0 {
+                continue;
             }
+            let start = b * seq_len * hidden_size + s * hidden_size;
+            tokens.push(hidden_data[start..start + hidden_size].to_vec());
         }
-        
-        // Divide by mask sum (with epsilon to avoid division by zero)
-        let mask_sum = mask_sum.max(1e-9);
-        for val in &mut sum_embedding {
-            *val /= mask_sum;
+        token_embeddings.push(tokens);
+    }
+
+    // Record the model's real hidden size so callers report the correct
+    // dimension regardless of which sentence-transformer is loaded.
+    state.dimensions = hidden_size;
+
+    Ok(token_embeddings)
+}
+
+/// Pool a text's per-token hidden states into a single vector and optionally
+/// L2-normalize it, according to the configured [`PoolingMode`].
+fn embed_batch_internal(texts: &[String]) -> Result<Vec<Vec<f32>>, EmbedError> {
+    let token_embeddings = embed_tokens_internal(texts)?;
+
+    let (pooling, normalize) = {
+        let state = MODEL_STATE.lock().map_err(|e| {
+            EmbedError::Inference(format!("Failed to lock model state: {}", e))
+        })?;
+        (state.pooling, state.normalize)
+    };
+
+    let embeddings = token_embeddings
+        .iter()
+        .map(|tokens| {
+            let mut pooled = match pooling {
+                PoolingMode::Mean => pool_mean(tokens),
+                PoolingMode::Cls => pool_cls(tokens),
+                PoolingMode::Max => pool_max(tokens),
+            };
+            if normalize {
+                l2_normalize(&mut pooled);
+            }
+            pooled
+        })
+        .collect();
+
+    Ok(embeddings)
+}
+
+/// Attention-masked mean: average over the real tokens.
+fn pool_mean(tokens: &[Vec<f32>]) -> Vec<f32> {
+    let hidden = tokens.first().map(|t| t.len()).unwrap_or(0);
+    let mut sum = vec![0.0f32; hidden];
+    for token in tokens {
+        for (acc, &v) in sum.iter_mut().zip(token) {
+            *acc += v;
         }
-        
-        // L2 normalize
-        let l2_norm: f32 = sum_embedding.iter().map(|v| v * v).sum::<f32>().sqrt();
-        if l2_norm > 1e-9 {
-            for val in &mut sum_embedding {
-                *val /= l2_norm;
+    }
+    let count = (tokens.len() as f32).max(1e-9);
+    for v in &mut sum {
+        *v /= count;
+    }
+    sum
+}
+
+/// CLS pooling: the hidden state at position 0, as recommended for BERT models.
+fn pool_cls(tokens: &[Vec<f32>]) -> Vec<f32> {
+    tokens.first().cloned().unwrap_or_default()
+}
+
+/// Elementwise max over the real tokens.
+fn pool_max(tokens: &[Vec<f32>]) -> Vec<f32> {
+    let hidden = tokens.first().map(|t| t.len()).unwrap_or(0);
+    let mut maxed = vec![f32::NEG_INFINITY; hidden];
+    for token in tokens {
+        for (acc, &v) in maxed.iter_mut().zip(token) {
+            if v > *acc {
+                *acc = v;
             }
         }
-        
-        embeddings.push(sum_embedding);
     }
+    // No tokens → return zeros rather than -inf.
+    if tokens.is_empty() {
+        return vec![0.0; hidden];
+    }
+    maxed
+}
 
-    Ok(embeddings)
+/// L2-normalize a vector in place (no-op for a near-zero vector).
+fn l2_normalize(vec: &mut [f32]) {
+    let l2_norm: f32 = vec.iter().map(|v| v * v).sum::<f32>().sqrt();
+    if l2_norm > 1e-9 {
+        for v in vec {
+            *v /= l2_norm;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pooling_mode_from_name() {
+        assert_eq!(PoolingMode::from_name("mean").unwrap(), PoolingMode::Mean);
+        assert_eq!(PoolingMode::from_name("CLS").unwrap(), PoolingMode::Cls);
+        assert_eq!(PoolingMode::from_name("Max").unwrap(), PoolingMode::Max);
+        assert!(PoolingMode::from_name("sum").is_err());
+    }
+
+    #[test]
+    fn pool_helpers_match_their_modes() {
+        let tokens = vec![vec![1.0, 4.0], vec![3.0, 2.0]];
+        assert_eq!(pool_mean(&tokens), vec![2.0, 3.0]);
+        assert_eq!(pool_cls(&tokens), vec![1.0, 4.0]);
+        assert_eq!(pool_max(&tokens), vec![3.0, 4.0]);
+    }
+
+    #[test]
+    fn pool_helpers_handle_empty_input() {
+        let empty: Vec<Vec<f32>> = Vec::new();
+        assert!(pool_mean(&empty).is_empty());
+        assert!(pool_cls(&empty).is_empty());
+        assert!(pool_max(&empty).is_empty());
+    }
 }
 
 /// Memento Core - Rust embeddings for Python
 #[pymodule]
 fn memento_core(m: &Bound<'_ , PyModule>) -> PyResult<()> {
     m.add("__version__", env!("CARGO_PKG_VERSION"))?;
-    
+
+    m.add_class::<EmbedderOptions>()?;
+    m.add_class::<store::Collection>()?;
     m.add_wrapped(wrap_pyfunction!(init_model))?;
     m.add_wrapped(wrap_pyfunction!(embed_text))?;
     m.add_wrapped(wrap_pyfunction!(embed_batch))?;
+    m.add_wrapped(wrap_pyfunction!(embed_text_tokens))?;
+    m.add_wrapped(wrap_pyfunction!(embed_batch_tokens))?;
     m.add_wrapped(wrap_pyfunction!(is_ready))?;
     m.add_wrapped(wrap_pyfunction!(get_model_info))?;
     
@@ -0,0 +1,386 @@
+//! Built-in vector store.
+//!
+//! A [`Collection`] holds `(id, embedding, metadata)` records and supports
+//! cosine-similarity search with optional metadata filtering. Embeddings need
+//! not be pre-normalized: scoring normalizes by vector magnitude so rankings
+//! are correct even when embeddings were produced with `normalize=False`.
+
+use pyo3::prelude::*;
+use pyo3::types::{PyDict, PyList};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fs::File;
+use std::io::{BufReader, BufWriter};
+
+use crate::{embed_text_internal, EmbedError};
+
+/// A single stored vector with its identifier and metadata.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Record {
+    id: String,
+    embedding: Vec<f32>,
+    metadata: Value,
+}
+
+/// An in-memory collection of embeddings with similarity search.
+#[pyclass]
+pub struct Collection {
+    records: Vec<Record>,
+}
+
+#[pymethods]
+impl Collection {
+    #[new]
+    fn new() -> Self {
+        Self { records: Vec::new() }
+    }
+
+    /// Number of records currently held.
+    fn __len__(&self) -> usize {
+        self.records.len()
+    }
+
+    /// Add a single record. A later `add` with an existing `id` replaces it.
+    #[pyo3(signature = (id, embedding, metadata=None))]
+    fn add(&mut self, id: String, embedding: Vec<f32>, metadata: Option<&Bound<'_, PyDict>>) -> PyResult<()> {
+        let metadata = match metadata {
+            Some(dict) => dict_to_json(dict)?,
+            None => Value::Object(Default::default()),
+        };
+        self.upsert(Record { id, embedding, metadata });
+        Ok(())
+    }
+
+    /// Add many records at once. `metadatas`, when given, must match `ids`.
+    #[pyo3(signature = (ids, embeddings, metadatas=None))]
+    fn add_batch(
+        &mut self,
+        ids: Vec<String>,
+        embeddings: Vec<Vec<f32>>,
+        metadatas: Option<Vec<Bound<'_, PyDict>>>,
+    ) -> PyResult<()> {
+        if ids.len() != embeddings.len() {
+            return Err(EmbedError::Inference(
+                "ids and embeddings must have the same length".to_string(),
+            ).into());
+        }
+        if let Some(ref metas) = metadatas {
+            if metas.len() != ids.len() {
+                return Err(EmbedError::Inference(
+                    "metadatas must have the same length as ids".to_string(),
+                ).into());
+            }
+        }
+
+        for (i, (id, embedding)) in ids.into_iter().zip(embeddings).enumerate() {
+            let metadata = match metadatas {
+                Some(ref metas) => dict_to_json(&metas[i])?,
+                None => Value::Object(Default::default()),
+            };
+            self.upsert(Record { id, embedding, metadata });
+        }
+        Ok(())
+    }
+
+    /// Search by query text or query vector, returning the top `top_k`
+    /// `(id, score, metadata)` tuples ranked by descending cosine similarity.
+    ///
+    /// `filter`, when given, is a metadata predicate applied before scoring
+    /// (see [`matches_filter`] for the supported operators).
+    #[pyo3(signature = (query, top_k=10, filter=None))]
+    fn search(
+        &self,
+        py: Python,
+        query: &Bound<'_, PyAny>,
+        top_k: usize,
+        filter: Option<&Bound<'_, PyDict>>,
+    ) -> PyResult<PyObject> {
+        // A query may be a raw vector or a text string to embed.
+        let query_vec: Vec<f32> = if let Ok(vec) = query.extract::<Vec<f32>>() {
+            vec
+        } else {
+            let text: String = query.extract()?;
+            embed_text_internal(&text)?
+        };
+
+        let filter = match filter {
+            Some(dict) => Some(dict_to_json(dict)?),
+            None => None,
+        };
+
+        let mut scored: Vec<(&Record, f32)> = self
+            .records
+            .iter()
+            .filter(|r| match &filter {
+                Some(f) => matches_filter(&r.metadata, f),
+                None => true,
+            })
+            .map(|r| (r, cosine(&query_vec, &r.embedding)))
+            .collect();
+
+        scored.sort_by(|a, b| b.1.total_cmp(&a.1));
+        scored.truncate(top_k);
+
+        let results = PyList::empty_bound(py);
+        for (record, score) in scored {
+            let tuple = (
+                record.id.clone(),
+                score,
+                json_to_py(py, &record.metadata)?,
+            );
+            results.append(tuple)?;
+        }
+        Ok(results.into())
+    }
+
+    /// Persist the collection to `path` as JSON.
+    ///
+    /// JSON is used rather than a compact binary format because `metadata` is a
+    /// `serde_json::Value`, which only round-trips through a self-describing
+    /// format (its `Deserialize` relies on `deserialize_any`).
+    fn save(&self, path: String) -> PyResult<()> {
+        let file = File::create(&path).map_err(EmbedError::Io)?;
+        let writer = BufWriter::new(file);
+        serde_json::to_writer(writer, &self.records)
+            .map_err(|e| EmbedError::Inference(format!("Failed to serialize collection: {}", e)))?;
+        Ok(())
+    }
+
+    /// Load a collection previously written with [`Collection::save`].
+    #[staticmethod]
+    fn load(path: String) -> PyResult<Self> {
+        let file = File::open(&path).map_err(EmbedError::Io)?;
+        let reader = BufReader::new(file);
+        let records: Vec<Record> = serde_json::from_reader(reader)
+            .map_err(|e| EmbedError::Inference(format!("Failed to deserialize collection: {}", e)))?;
+        Ok(Self { records })
+    }
+}
+
+impl Collection {
+    /// Insert `record`, replacing any existing record with the same id.
+    fn upsert(&mut self, record: Record) {
+        if let Some(existing) = self.records.iter_mut().find(|r| r.id == record.id) {
+            *existing = record;
+        } else {
+            self.records.push(record);
+        }
+    }
+}
+
+/// Dot product of two vectors. The shorter of the two lengths is used so
+/// mismatched dims don't panic.
+fn dot(a: &[f32], b: &[f32]) -> f32 {
+    a.iter().zip(b).map(|(x, y)| x * y).sum()
+}
+
+/// Cosine similarity of two vectors, normalizing by magnitude so inputs need
+/// not be pre-normalized. Returns `0.0` if either vector has zero magnitude.
+fn cosine(a: &[f32], b: &[f32]) -> f32 {
+    let norm_a = dot(a, a).sqrt();
+    let norm_b = dot(b, b).sqrt();
+    if norm_a == 0.0 || norm_b == 0.0 {
+        return 0.0;
+    }
+    dot(a, b) / (norm_a * norm_b)
+}
+
+/// Evaluate a metadata `filter` against a record's `metadata`.
+///
+/// Each key in `filter` maps either to a literal (equality) or to an object of
+/// comparison operators: `$eq`, `$ne`, `$gt`, `$gte`, `$lt`, `$lte`. All keys
+/// must match for the record to be kept.
+fn matches_filter(metadata: &Value, filter: &Value) -> bool {
+    let (Value::Object(meta), Value::Object(conds)) = (metadata, filter) else {
+        return false;
+    };
+
+    for (field, cond) in conds {
+        let actual = meta.get(field);
+        let ok = match cond {
+            Value::Object(ops) => ops.iter().all(|(op, expected)| compare(actual, op, expected)),
+            literal => actual.map(|v| v == literal).unwrap_or(false),
+        };
+        if !ok {
+            return false;
+        }
+    }
+    true
+}
+
+/// Evaluate a single comparison operator against the actual metadata value.
+fn compare(actual: Option<&Value>, op: &str, expected: &Value) -> bool {
+    let Some(actual) = actual else { return false };
+    match op {
+        "$eq" => actual == expected,
+        "$ne" => actual != expected,
+        "$gt" | "$gte" | "$lt" | "$lte" => {
+            match (actual.as_f64(), expected.as_f64()) {
+                (Some(a), Some(b)) => match op {
+                    "$gt" => a > b,
+                    "$gte" => a >= b,
+                    "$lt" => a < b,
+                    _ => a <= b,
+                },
+                _ => false,
+            }
+        }
+        _ => false,
+    }
+}
+
+/// Convert a Python dict into a JSON object value.
+fn dict_to_json(dict: &Bound<'_, PyDict>) -> PyResult<Value> {
+    let mut map = serde_json::Map::with_capacity(dict.len());
+    for (key, value) in dict.iter() {
+        map.insert(key.extract::<String>()?, any_to_json(&value)?);
+    }
+    Ok(Value::Object(map))
+}
+
+/// Convert an arbitrary Python value into a JSON value.
+fn any_to_json(value: &Bound<'_, PyAny>) -> PyResult<Value> {
+    if value.is_none() {
+        Ok(Value::Null)
+    } else if let Ok(b) = value.extract::<bool>() {
+        Ok(Value::Bool(b))
+    } else if let Ok(i) = value.extract::<i64>() {
+        Ok(Value::from(i))
+    } else if let Ok(f) = value.extract::<f64>() {
+        Ok(Value::from(f))
+    } else if let Ok(s) = value.extract::<String>() {
+        Ok(Value::String(s))
+    } else if let Ok(list) = value.downcast::<PyList>() {
+        let items = list.iter().map(|v| any_to_json(&v)).collect::<PyResult<Vec<_>>>()?;
+        Ok(Value::Array(items))
+    } else if let Ok(dict) = value.downcast::<PyDict>() {
+        dict_to_json(dict)
+    } else {
+        Err(EmbedError::Inference(format!(
+            "Unsupported metadata value type: {}",
+            value.get_type().name()?
+        )).into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn record(id: &str, embedding: Vec<f32>, metadata: Value) -> Record {
+        Record { id: id.to_string(), embedding, metadata }
+    }
+
+    #[test]
+    fn equality_and_comparison_operators() {
+        let meta = json!({"year": 2020, "lang": "rust"});
+
+        // Bare literal is equality.
+        assert!(matches_filter(&meta, &json!({"lang": "rust"})));
+        assert!(!matches_filter(&meta, &json!({"lang": "go"})));
+
+        // Comparison operators on numbers.
+        assert!(matches_filter(&meta, &json!({"year": {"$gte": 2020}})));
+        assert!(matches_filter(&meta, &json!({"year": {"$lt": 2021}})));
+        assert!(!matches_filter(&meta, &json!({"year": {"$gt": 2020}})));
+        assert!(matches_filter(&meta, &json!({"year": {"$ne": 1999}})));
+
+        // All keys must match.
+        assert!(!matches_filter(&meta, &json!({"year": 2020, "lang": "go"})));
+    }
+
+    #[test]
+    fn missing_field_never_matches() {
+        let meta = json!({"year": 2020});
+        assert!(!matches_filter(&meta, &json!({"lang": "rust"})));
+        assert!(!matches_filter(&meta, &json!({"lang": {"$eq": "rust"}})));
+        // A missing field fails comparison operators rather than panicking.
+        assert!(!compare(None, "$gt", &json!(1)));
+    }
+
+    #[test]
+    fn dot_uses_shorter_length() {
+        // Mismatched dims must not panic; extra trailing components are ignored.
+        assert_eq!(dot(&[1.0, 2.0, 3.0], &[1.0, 1.0]), 3.0);
+    }
+
+    #[test]
+    fn cosine_is_magnitude_invariant() {
+        // Parallel vectors of different magnitude score ~1.0 even unnormalized.
+        let close = cosine(&[1.0, 0.0], &[5.0, 0.0]);
+        let far = cosine(&[1.0, 0.0], &[0.0, 5.0]);
+        assert!((close - 1.0).abs() < 1e-6);
+        assert!(far.abs() < 1e-6);
+        assert!(close > far);
+        // Zero vectors score 0 rather than NaN.
+        assert_eq!(cosine(&[0.0, 0.0], &[1.0, 1.0]), 0.0);
+    }
+
+    #[test]
+    fn upsert_replaces_existing_id() {
+        let mut col = Collection::new();
+        col.upsert(record("a", vec![1.0], json!({"v": 1})));
+        col.upsert(record("a", vec![2.0], json!({"v": 2})));
+        assert_eq!(col.records.len(), 1);
+        assert_eq!(col.records[0].embedding, vec![2.0]);
+        assert_eq!(col.records[0].metadata, json!({"v": 2}));
+    }
+
+    #[test]
+    fn save_load_round_trip_preserves_records() {
+        let mut col = Collection::new();
+        col.upsert(record("a", vec![1.0, 0.0], json!({"lang": "rust", "year": 2020})));
+        col.upsert(record("b", vec![0.0, 1.0], json!({"lang": "go"})));
+
+        let mut path = std::env::temp_dir();
+        path.push("memento_store_round_trip.json");
+        let path = path.to_str().unwrap().to_string();
+
+        col.save(path.clone()).expect("save");
+        let loaded = Collection::load(path).expect("load");
+
+        assert_eq!(loaded.records.len(), 2);
+        assert_eq!(loaded.records[0].id, "a");
+        assert_eq!(loaded.records[0].metadata, json!({"lang": "rust", "year": 2020}));
+
+        // Ranking against the loaded store still works end-to-end.
+        let query = vec![1.0, 0.0];
+        let best = loaded
+            .records
+            .iter()
+            .max_by(|x, y| cosine(&query, &x.embedding).total_cmp(&cosine(&query, &y.embedding)))
+            .unwrap();
+        assert_eq!(best.id, "a");
+    }
+}
+
+/// Convert a JSON value back into a Python object.
+fn json_to_py(py: Python, value: &Value) -> PyResult<PyObject> {
+    Ok(match value {
+        Value::Null => py.None(),
+        Value::Bool(b) => b.into_py(py),
+        Value::Number(n) => {
+            if let Some(i) = n.as_i64() {
+                i.into_py(py)
+            } else {
+                n.as_f64().unwrap_or(0.0).into_py(py)
+            }
+        }
+        Value::String(s) => s.into_py(py),
+        Value::Array(items) => {
+            let list = PyList::empty_bound(py);
+            for item in items {
+                list.append(json_to_py(py, item)?)?;
+            }
+            list.into()
+        }
+        Value::Object(map) => {
+            let dict = PyDict::new_bound(py);
+            for (k, v) in map {
+                dict.set_item(k, json_to_py(py, v)?)?;
+            }
+            dict.into()
+        }
+    })
+}